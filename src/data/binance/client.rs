@@ -11,6 +11,15 @@ use chrono::{DateTime, Utc};
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use polars::prelude::*;
+use strum::{Display, EnumString};
+use tokio::time::{sleep, Duration};
+
+use super::super::exchange::{canonical_symbol, MarketDataSource};
+use super::super::storage::CandleStore;
+
+// Binance weights the `limit` param at 1, but hundreds of sequential pages
+// for a multi-month backfill still add up, so pace ourselves between pages.
+const BACKFILL_PAGE_DELAY: Duration = Duration::from_millis(250);
 
 // Types
 type Kline = (i64, String, String, String, String, String, i64, String, i32, String, String, String);
@@ -48,16 +57,20 @@ impl TryFrom<Klines> for DataFrame {
         let mut close: Vec<f32> = Vec::with_capacity(n_rows);
         let mut volume: Vec<f32> = Vec::with_capacity(n_rows);
 
+        let parse_field = |column: &'static str, value: String| -> Result<f32, BinanceError> {
+            value.parse().map_err(|_| BinanceError::ParseField { column, value })
+        };
+
         for kline in klines {
             let (_open_time, _open, _high, _low, _close, _volume, _close_time, _, _, _, _, _) = kline;
 
             open_time.push(_open_time);
             close_time.push(_close_time);
-            open.push(_open.parse().unwrap());
-            high.push(_high.parse().unwrap());
-            low.push(_low.parse().unwrap());
-            close.push(_close.parse().unwrap());
-            volume.push(_volume.parse().unwrap());
+            open.push(parse_field("open", _open)?);
+            high.push(parse_field("high", _high)?);
+            low.push(parse_field("low", _low)?);
+            close.push(parse_field("close", _close)?);
+            volume.push(parse_field("volume", _volume)?);
         }
 
         let cols = vec![
@@ -79,52 +92,66 @@ pub enum BinanceError {
     #[error("Symbol not found: {0}")]
     SymbolNotFound(String),
 
-    #[error("Interval not found")]
-    IntervalNotFound,
+    #[error("Error parsing field {column}: {value:?}")]
+    ParseField { column: &'static str, value: String },
 
     #[error("Error executing request: {0}")]
     RequestFailure(#[from] reqwest::Error),
 
     #[error("Error parsing response: {0}")]
     ToDataFrameError(#[from] PolarsError),
-}
 
-enum Interval {
-    Minutes(u8),
-    Hours(u8),
-    Days(u8),
-    Weeks(u8),
-    Months(u8),
-}
+    #[error("WebSocket error: {0}")]
+    WebSocketError(String),
 
+    #[error("Encoding error: {0}")]
+    EncodingError(String),
 
-impl TryFrom<Interval> for &'static str {
-    type Error = BinanceError;
+    #[error("Storage error: {0}")]
+    StorageError(String),
+}
 
-    fn try_from(interval: Interval) -> Result<Self, Self::Error> {
-        match interval {
-            Interval::Minutes(1) => Ok("1m"),
-            Interval::Minutes(3) => Ok("3m"),
-            Interval::Minutes(5) => Ok("5m"),
-            Interval::Minutes(15) => Ok("15m"),
-            Interval::Minutes(30) => Ok("30m"),
-            Interval::Hours(1) => Ok("1h"),
-            Interval::Hours(2) => Ok("2h"),
-            Interval::Hours(4) => Ok("4h"),
-            Interval::Hours(6) => Ok("6h"),
-            Interval::Hours(8) => Ok("8h"),
-            Interval::Hours(12) => Ok("12h"),
-            Interval::Days(1) => Ok("1d"),
-            Interval::Days(3) => Ok("3d"),
-            Interval::Weeks(1) => Ok("1w"),
-            Interval::Months(1) => Ok("1M"),
-            _ => Err(BinanceError::IntervalNotFound),
-        }
-    }
+/// A Binance kline interval. `Display` serializes each variant to Binance's
+/// exact query-param string (e.g. `1m`, `4h`, `1M`), and `FromStr` is its
+/// inverse, so the full set of intervals Binance supports round-trips
+/// without the old hand-picked numeric `TryFrom` silently rejecting anything
+/// it didn't enumerate (`Minutes(2)`, say).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display, EnumString)]
+pub(crate) enum Interval {
+    #[strum(serialize = "1m")]
+    OneMinute,
+    #[strum(serialize = "3m")]
+    ThreeMinutes,
+    #[strum(serialize = "5m")]
+    FiveMinutes,
+    #[strum(serialize = "15m")]
+    FifteenMinutes,
+    #[strum(serialize = "30m")]
+    ThirtyMinutes,
+    #[strum(serialize = "1h")]
+    OneHour,
+    #[strum(serialize = "2h")]
+    TwoHours,
+    #[strum(serialize = "4h")]
+    FourHours,
+    #[strum(serialize = "6h")]
+    SixHours,
+    #[strum(serialize = "8h")]
+    EightHours,
+    #[strum(serialize = "12h")]
+    TwelveHours,
+    #[strum(serialize = "1d")]
+    OneDay,
+    #[strum(serialize = "3d")]
+    ThreeDays,
+    #[strum(serialize = "1w")]
+    OneWeek,
+    #[strum(serialize = "1M")]
+    OneMonth,
 }
 
 
-struct BinanceClient {
+pub(crate) struct BinanceClient {
     client: Client,
     exchange: Exchange,
 }
@@ -150,30 +177,216 @@ impl BinanceClient {
         )
     }
 
+    /// Backfills historical klines for `symbol` between `from` and `to`.
+    ///
+    /// Binance caps a single `/api/v3/klines` response at 1000 rows, so a
+    /// multi-month pull requires paginating on `startTime`/`endTime`: after
+    /// each batch we resume from the last returned `open_time + 1`, and stop
+    /// once we reach `to` (or the API hands back a short, final page). When
+    /// `to` is `None` the pagination runs up to the present.
     async fn get_symbol_data(
         &self,
         symbol: &str,
         interval: Interval,
-        from: DateTime<Utc>
+        from: DateTime<Utc>,
+        to: Option<DateTime<Utc>>,
     ) -> Result<DataFrame, BinanceError> {
         self.exchange.symbols.get(symbol).ok_or(BinanceError::SymbolNotFound(symbol.to_owned()))?;
 
-        let interval_str = interval.try_into()?;
+        let interval_str = interval.to_string();
+        let end_time = to.map(|to| to.timestamp_millis());
+
+        let mut start_time = from.timestamp_millis();
+        let mut df: Option<DataFrame> = None;
+
+        loop {
+            let mut query = vec![
+                ("symbol", symbol.to_owned()),
+                ("interval", interval_str.to_owned()),
+                ("limit", "1000".to_owned()),
+                ("startTime", start_time.to_string()),
+            ];
+
+            if let Some(end_time) = end_time {
+                query.push(("endTime", end_time.to_string()));
+            }
+
+            let batch: Vec<Kline> = self
+                .client
+                .get("https://api.binance.com/api/v3/klines")
+                .query(&query)
+                .send()
+                .await?
+                .json()
+                .await?;
+
+            let n_rows = batch.len();
+
+            if n_rows == 0 {
+                break;
+            }
+
+            let last_open_time = batch[n_rows - 1].0;
+            let page: DataFrame = Klines::from(batch).try_into()?;
+
+            df = Some(match df {
+                Some(mut existing) => {
+                    existing.vstack_mut(&page)?;
+                    existing
+                }
+                None => page,
+            });
+
+            let reached_end = end_time.is_some_and(|end_time| last_open_time >= end_time);
+
+            if n_rows < 1000 || reached_end {
+                break;
+            }
+
+            start_time = last_open_time + 1;
+
+            sleep(BACKFILL_PAGE_DELAY).await;
+        }
+
+        Ok(df.unwrap_or_default())
+    }
+
+    /// Fetches an order book snapshot for `symbol` via `GET /api/v3/depth`.
+    ///
+    /// `limit` is capped by Binance at 5000; pass `None` to use its default
+    /// of 100.
+    async fn get_depth(&self, symbol: &str, limit: Option<u32>) -> Result<OrderBook, BinanceError> {
+        self.exchange.symbols.get(symbol).ok_or(BinanceError::SymbolNotFound(symbol.to_owned()))?;
+
+        let limit = limit.unwrap_or(100).to_string();
 
-        let klines: Klines = self
+        let depth: DepthData = self
             .client
-            .get("https://api.binance.com/api/v3/klines")
-            .query(&[("symbol", symbol), ("interval", interval_str), ("limit", "1000")])
+            .get("https://api.binance.com/api/v3/depth")
+            .query(&[("symbol", symbol), ("limit", &limit)])
             .send()
             .await?
-            .json::<Vec<Kline>>()
-            .await?
-            .into();
+            .json()
+            .await?;
+
+        depth.try_into()
+    }
+
+    /// Backfills `symbol`/`interval` into `store`, requesting only candles
+    /// newer than what's already stored there, so repeated runs are cheap
+    /// and idempotent rather than re-downloading the whole history.
+    async fn backfill<S: CandleStore>(
+        &self,
+        symbol: &str,
+        interval: Interval,
+        store: &S,
+    ) -> Result<(), BinanceError> {
+        let from = match store.last_open_time(symbol, interval).await? {
+            Some(last_open_time) => DateTime::<Utc>::from_timestamp_millis(last_open_time + 1)
+                .ok_or_else(|| BinanceError::StorageError(format!("invalid stored open_time: {last_open_time}")))?,
+            None => DateTime::<Utc>::from_timestamp(0, 0).expect("epoch is a valid timestamp"),
+        };
+
+        let candles = self.get_symbol_data(symbol, interval, from, None).await?;
+
+        if candles.height() > 0 {
+            store.insert(symbol, interval, &candles).await?;
+        }
 
-        Ok(klines.try_into()?)
+        Ok(())
+    }
+
+    /// Resolves a canonical `BASE-QUOTE` symbol back to Binance's own
+    /// smashed-together spelling (e.g. `BTC-USDT` -> `BTCUSDT`).
+    fn raw_symbol(&self, canonical: &str) -> Result<String, BinanceError> {
+        self.exchange
+            .symbols
+            .values()
+            .find(|symbol| canonical_symbol(&symbol.base_asset, &symbol.quote_asset) == canonical)
+            .map(|symbol| symbol.symbol.clone())
+            .ok_or_else(|| BinanceError::SymbolNotFound(canonical.to_owned()))
     }
 }
 
+impl MarketDataSource for BinanceClient {
+    type Error = BinanceError;
+
+    async fn symbols(&self) -> Result<Vec<String>, BinanceError> {
+        Ok(self
+            .exchange
+            .symbols
+            .values()
+            .map(|symbol| canonical_symbol(&symbol.base_asset, &symbol.quote_asset))
+            .collect())
+    }
+
+    async fn klines(
+        &self,
+        symbol: &str,
+        interval: Interval,
+        from: DateTime<Utc>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<DataFrame, BinanceError> {
+        let raw_symbol = self.raw_symbol(symbol)?;
+        self.get_symbol_data(&raw_symbol, interval, from, to).await
+    }
+}
+
+
+#[derive(Debug, Deserialize)]
+struct DepthData {
+    #[serde(rename = "lastUpdateId")]
+    last_update_id: u64,
+    bids: Vec<(String, String)>,
+    asks: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+struct OrderBook {
+    last_update_id: u64,
+    bids: Vec<(f64, f64)>,
+    asks: Vec<(f64, f64)>,
+}
+
+impl TryFrom<DepthData> for OrderBook {
+    type Error = BinanceError;
+
+    fn try_from(data: DepthData) -> Result<Self, Self::Error> {
+        let parse_field = |column: &'static str, value: String| -> Result<f64, BinanceError> {
+            value.parse().map_err(|_| BinanceError::ParseField { column, value })
+        };
+
+        let parse_side = |side: Vec<(String, String)>| -> Result<Vec<(f64, f64)>, BinanceError> {
+            side.into_iter()
+                .map(|(price, quantity)| Ok((parse_field("price", price)?, parse_field("quantity", quantity)?)))
+                .collect()
+        };
+
+        Ok(Self {
+            last_update_id: data.last_update_id,
+            bids: parse_side(data.bids)?,
+            asks: parse_side(data.asks)?,
+        })
+    }
+}
+
+impl OrderBook {
+    /// Converts this snapshot into a `(bids, asks)` pair of two-column
+    /// `price`/`quantity` `DataFrame`s, so depth snapshots fit the same
+    /// analysis pipeline as klines.
+    fn to_dataframe(&self) -> Result<(DataFrame, DataFrame), BinanceError> {
+        let side_to_df = |side: &[(f64, f64)]| -> Result<DataFrame, BinanceError> {
+            let (prices, quantities): (Vec<f64>, Vec<f64>) = side.iter().copied().unzip();
+
+            Ok(DataFrame::new(vec![
+                Series::new("price", prices),
+                Series::new("quantity", quantities),
+            ])?)
+        };
+
+        Ok((side_to_df(&self.bids)?, side_to_df(&self.asks)?))
+    }
+}
 
 #[derive(Debug, Deserialize, Serialize)]
 struct ExchangeData {
@@ -253,41 +466,6 @@ async fn get_exchange(client: &Client) -> Result<Exchange, BinanceError> {
 }
 
 
-fn klines_to_columns(klines: Vec<Kline>) -> Vec<Series> {
-    let n_rows = klines.len();
-
-    let mut open_time: Vec<i64> = Vec::with_capacity(n_rows);
-    let mut close_time: Vec<i64> = Vec::with_capacity(n_rows);
-    let mut open: Vec<f32> = Vec::with_capacity(n_rows);
-    let mut high: Vec<f32> = Vec::with_capacity(n_rows);
-    let mut low: Vec<f32> = Vec::with_capacity(n_rows);
-    let mut close: Vec<f32> = Vec::with_capacity(n_rows);
-    let mut volume: Vec<f32> = Vec::with_capacity(n_rows);
-
-    for kline in klines {
-        let (_open_time, _open, _high, _low, _close, _volume, _close_time, _, _, _, _, _) = kline;
-
-        open_time.push(_open_time);
-        close_time.push(_close_time);
-        open.push(_open.parse().unwrap());
-        high.push(_high.parse().unwrap());
-        low.push(_low.parse().unwrap());
-        close.push(_close.parse().unwrap());
-        volume.push(_volume.parse().unwrap());
-    }
-
-    vec![
-        Series::new("open_time", open_time),
-        Series::new("close_time", close_time),
-        Series::new("open", open),
-        Series::new("high", high),
-        Series::new("low", low),
-        Series::new("close", close),
-        Series::new("volume", volume),
-    ]
-}
-
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -296,7 +474,7 @@ mod tests {
     async fn test_get_frame() -> Result<(), BinanceError> {
         let client = BinanceClient::new().await?;
 
-        let frame = client.get_symbol_data("BTCUSDT", Interval::Minutes(1), Utc::now()).await?;
+        let frame = client.get_symbol_data("BTCUSDT", Interval::OneMinute, Utc::now(), None).await?;
 
         println!("{:#?}", frame);
 
@@ -331,12 +509,46 @@ mod tests {
             .json::<Vec<Kline>>()
             .await?;
 
-        let cols = klines_to_columns(info);
-
-        let df = DataFrame::new(cols)?;
+        let df: DataFrame = Klines::from(info).try_into()?;
 
         println!("{:#?}", df);
 
         Ok(())
     }
+
+    #[test]
+    fn malformed_kline_field_reports_parse_field_error_instead_of_panicking() {
+        let klines: Klines = vec![(
+            1_700_000_000_000,
+            "not-a-number".to_owned(),
+            "42.0".to_owned(),
+            "41.0".to_owned(),
+            "41.5".to_owned(),
+            "100.0".to_owned(),
+            1_700_000_059_999,
+            "0".to_owned(),
+            0,
+            "0".to_owned(),
+            "0".to_owned(),
+            "0".to_owned(),
+        )]
+        .into();
+
+        let result: Result<DataFrame, BinanceError> = klines.try_into();
+
+        assert!(matches!(result, Err(BinanceError::ParseField { column: "open", .. })));
+    }
+
+    #[test]
+    fn malformed_depth_field_reports_parse_field_error_instead_of_panicking() {
+        let data = DepthData {
+            last_update_id: 1,
+            bids: vec![("not-a-number".to_owned(), "1.0".to_owned())],
+            asks: vec![],
+        };
+
+        let result = OrderBook::try_from(data);
+
+        assert!(matches!(result, Err(BinanceError::ParseField { column: "price", .. })));
+    }
 }
@@ -0,0 +1,7 @@
+//! Binance market data: the REST client and the live WebSocket feed.
+
+mod client;
+mod streaming;
+
+pub(crate) use client::{BinanceClient, BinanceError, Interval};
+pub(crate) use streaming::{KlineEvent, KlineStream, MarketStream, TradeEvent, TradeStream};
@@ -0,0 +1,254 @@
+//! Real-time market data over Binance's public WebSocket feed.
+//!
+//! `BinanceClient` only covers REST polling, which is wasteful for anything
+//! that wants to react to every kline/trade as it happens. This module opens
+//! `wss://stream.binance.com:9443/ws/<symbol>@<channel>` instead and exposes
+//! the push frames as a typed `Stream`, behind the same kind of pluggable
+//! `MarketStream` trait a `LatestRate`-style source would use, so a strategy
+//! can depend on the trait and swap a live feed for a replay feed in tests.
+
+use std::pin::Pin;
+use std::time::Duration;
+
+use futures_util::{SinkExt, Stream, StreamExt};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+use super::client::{BinanceError, Interval};
+
+const STREAM_BASE_URL: &str = "wss://stream.binance.com:9443/ws";
+const CHANNEL_CAPACITY: usize = 256;
+const RECONNECT_BACKOFF: [Duration; 4] = [
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+];
+
+/// A source of live market data, subscribable by symbol, yielding a typed
+/// stream of push events. Implemented here by the real Binance feed
+/// (`KlineStream`, `TradeStream`); a replay feed can implement it as well,
+/// so strategies depend on the trait rather than a concrete transport.
+pub(crate) trait MarketStream {
+    type Item: Send + 'static;
+
+    fn subscribe(
+        symbol: &str,
+        interval: Interval,
+    ) -> Pin<Box<dyn Stream<Item = Result<Self::Item, BinanceError>> + Send>>;
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct KlineEvent {
+    #[serde(rename = "E")]
+    pub event_time: i64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "k")]
+    pub kline: KlinePayload,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct KlinePayload {
+    #[serde(rename = "t")]
+    pub open_time: i64,
+    #[serde(rename = "T")]
+    pub close_time: i64,
+    #[serde(rename = "o")]
+    pub open: String,
+    #[serde(rename = "h")]
+    pub high: String,
+    #[serde(rename = "l")]
+    pub low: String,
+    #[serde(rename = "c")]
+    pub close: String,
+    #[serde(rename = "v")]
+    pub volume: String,
+    #[serde(rename = "x")]
+    pub is_closed: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct TradeEvent {
+    #[serde(rename = "E")]
+    pub event_time: i64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "t")]
+    pub trade_id: i64,
+    #[serde(rename = "p")]
+    pub price: String,
+    #[serde(rename = "q")]
+    pub quantity: String,
+    #[serde(rename = "T")]
+    pub trade_time: i64,
+    #[serde(rename = "m")]
+    pub is_buyer_maker: bool,
+}
+
+pub(crate) struct KlineStream;
+
+impl MarketStream for KlineStream {
+    type Item = KlineEvent;
+
+    fn subscribe(
+        symbol: &str,
+        interval: Interval,
+    ) -> Pin<Box<dyn Stream<Item = Result<Self::Item, BinanceError>> + Send>> {
+        let stream_name = format!("{}@kline_{}", symbol.to_lowercase(), interval);
+        Box::pin(spawn_stream(stream_name, |text| serde_json::from_str(text).ok()))
+    }
+}
+
+pub(crate) struct TradeStream;
+
+impl MarketStream for TradeStream {
+    type Item = TradeEvent;
+
+    fn subscribe(
+        symbol: &str,
+        _interval: Interval,
+    ) -> Pin<Box<dyn Stream<Item = Result<Self::Item, BinanceError>> + Send>> {
+        let stream_name = format!("{}@trade", symbol.to_lowercase());
+        Box::pin(spawn_stream(stream_name, |text| serde_json::from_str(text).ok()))
+    }
+}
+
+/// Connects to `<stream_name>`, decoding each text frame with `parse` and
+/// forwarding the result over a channel. Responds to server `ping` frames
+/// with `pong`, and on disconnect reconnects with backoff, re-subscribing by
+/// re-opening the same stream name.
+fn spawn_stream<T, F>(stream_name: String, parse: F) -> ReceiverStream<Result<T, BinanceError>>
+where
+    T: Send + 'static,
+    F: Fn(&str) -> Option<T> + Send + 'static,
+{
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        let url = format!("{}/{}", STREAM_BASE_URL, stream_name);
+        let mut attempt = 0usize;
+
+        loop {
+            match connect_async(&url).await {
+                Ok((mut socket, _)) => {
+                    attempt = 0;
+
+                    while let Some(message) = socket.next().await {
+                        match message {
+                            Ok(Message::Text(text)) => {
+                                if let Some(event) = parse(&text) {
+                                    if tx.send(Ok(event)).await.is_err() {
+                                        return;
+                                    }
+                                }
+                            }
+                            Ok(Message::Ping(payload)) => {
+                                if socket.send(Message::Pong(payload)).await.is_err() {
+                                    break;
+                                }
+                            }
+                            Ok(Message::Close(_)) | Err(_) => break,
+                            Ok(_) => {}
+                        }
+                    }
+                }
+                Err(err) => {
+                    if tx.send(Err(BinanceError::WebSocketError(err.to_string()))).await.is_err() {
+                        return;
+                    }
+                }
+            }
+
+            let backoff = RECONNECT_BACKOFF[attempt.min(RECONNECT_BACKOFF.len() - 1)];
+            attempt += 1;
+            sleep(backoff).await;
+        }
+    });
+
+    ReceiverStream::new(rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kline_event_deserializes_wire_field_names() {
+        let text = r#"{
+            "E": 1700000000000,
+            "s": "BTCUSDT",
+            "k": {
+                "t": 1700000000000,
+                "T": 1700000059999,
+                "o": "41.5",
+                "h": "42.0",
+                "l": "41.0",
+                "c": "41.8",
+                "v": "100.0",
+                "x": true
+            }
+        }"#;
+
+        let event: KlineEvent = serde_json::from_str(text).unwrap();
+
+        assert_eq!(event.event_time, 1_700_000_000_000);
+        assert_eq!(event.symbol, "BTCUSDT");
+        assert_eq!(event.kline.open_time, 1_700_000_000_000);
+        assert_eq!(event.kline.close_time, 1_700_000_059_999);
+        assert_eq!(event.kline.close, "41.8");
+        assert!(event.kline.is_closed);
+    }
+
+    #[test]
+    fn kline_payload_is_closed_reflects_false_for_an_in_progress_candle() {
+        let text = r#"{
+            "t": 0, "T": 59999,
+            "o": "1.0", "h": "1.0", "l": "1.0", "c": "1.0", "v": "1.0",
+            "x": false
+        }"#;
+
+        let payload: KlinePayload = serde_json::from_str(text).unwrap();
+
+        assert!(!payload.is_closed);
+    }
+
+    #[test]
+    fn trade_event_deserializes_wire_field_names() {
+        let text = r#"{
+            "E": 1700000000000,
+            "s": "BTCUSDT",
+            "t": 12345,
+            "p": "41.5",
+            "q": "0.01",
+            "T": 1700000000001,
+            "m": true
+        }"#;
+
+        let event: TradeEvent = serde_json::from_str(text).unwrap();
+
+        assert_eq!(event.event_time, 1_700_000_000_000);
+        assert_eq!(event.symbol, "BTCUSDT");
+        assert_eq!(event.trade_id, 12345);
+        assert_eq!(event.price, "41.5");
+        assert_eq!(event.quantity, "0.01");
+        assert_eq!(event.trade_time, 1_700_000_000_001);
+        assert!(event.is_buyer_maker);
+    }
+
+    #[test]
+    fn trade_event_is_buyer_maker_reflects_false() {
+        let text = r#"{
+            "E": 0, "s": "BTCUSDT", "t": 1,
+            "p": "1.0", "q": "1.0", "T": 0, "m": false
+        }"#;
+
+        let event: TradeEvent = serde_json::from_str(text).unwrap();
+
+        assert!(!event.is_buyer_maker);
+    }
+}
@@ -0,0 +1,187 @@
+//! A client that manages calls to the Coinbase Exchange API.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration, Utc};
+use polars::prelude::*;
+use reqwest::Client;
+use serde::Deserialize;
+use thiserror::Error;
+
+use super::super::binance::Interval;
+use super::super::exchange::{canonical_symbol, MarketDataSource};
+
+// Coinbase caps a single candles response at 300 rows.
+const MAX_CANDLES_PER_PAGE: i64 = 300;
+
+type Candle = (i64, f64, f64, f64, f64, f64);
+
+#[derive(Debug, Error)]
+pub(crate) enum CoinbaseError {
+    #[error("Product not found: {0}")]
+    ProductNotFound(String),
+
+    #[error("Interval not supported by Coinbase candles: {0}")]
+    UnsupportedInterval(Interval),
+
+    #[error("Error executing request: {0}")]
+    RequestFailure(#[from] reqwest::Error),
+
+    #[error("Error parsing response: {0}")]
+    ToDataFrameError(#[from] PolarsError),
+}
+
+#[derive(Debug, Deserialize)]
+struct Product {
+    id: String,
+    base_currency: String,
+    quote_currency: String,
+}
+
+pub(crate) struct CoinbaseClient {
+    client: Client,
+    products: HashMap<String, Product>,
+}
+
+impl CoinbaseClient {
+    pub(crate) async fn new() -> Result<Self, CoinbaseError> {
+        let client = Client::new();
+
+        let products: Vec<Product> = client
+            .get("https://api.exchange.coinbase.com/products")
+            .send()
+            .await?
+            .json()
+            .await?;
+
+        let products = products.into_iter().map(|product| (product.id.clone(), product)).collect();
+
+        Ok(Self { client, products })
+    }
+
+    /// Resolves a canonical `BASE-QUOTE` symbol back to Coinbase's own
+    /// product id (which, unlike Binance's, is already `BASE-QUOTE`-shaped).
+    fn raw_symbol(&self, canonical: &str) -> Result<&str, CoinbaseError> {
+        self.products
+            .values()
+            .find(|product| canonical_symbol(&product.base_currency, &product.quote_currency) == canonical)
+            .map(|product| product.id.as_str())
+            .ok_or_else(|| CoinbaseError::ProductNotFound(canonical.to_owned()))
+    }
+}
+
+/// Maps a shared `Interval` onto one of Coinbase's candle granularities
+/// (in seconds). Coinbase only supports a handful of fixed granularities, so
+/// any interval outside that set (e.g. `3m` or `4h`) is rejected.
+fn granularity_seconds(interval: Interval) -> Result<i64, CoinbaseError> {
+    match interval {
+        Interval::OneMinute => Ok(60),
+        Interval::FiveMinutes => Ok(300),
+        Interval::FifteenMinutes => Ok(900),
+        Interval::OneHour => Ok(3600),
+        Interval::SixHours => Ok(21_600),
+        Interval::OneDay => Ok(86_400),
+        other => Err(CoinbaseError::UnsupportedInterval(other)),
+    }
+}
+
+fn candles_to_dataframe(mut candles: Vec<Candle>, granularity: i64) -> Result<DataFrame, CoinbaseError> {
+    // Coinbase returns each page newest-first; sort ascending so a page's
+    // rows -- and the concatenation of all of them -- are chronological,
+    // matching the Binance client's output well enough to merge the two.
+    candles.sort_by_key(|candle| candle.0);
+
+    let n_rows = candles.len();
+
+    let mut open_time: Vec<i64> = Vec::with_capacity(n_rows);
+    let mut close_time: Vec<i64> = Vec::with_capacity(n_rows);
+    let mut open: Vec<f32> = Vec::with_capacity(n_rows);
+    let mut high: Vec<f32> = Vec::with_capacity(n_rows);
+    let mut low: Vec<f32> = Vec::with_capacity(n_rows);
+    let mut close: Vec<f32> = Vec::with_capacity(n_rows);
+    let mut volume: Vec<f32> = Vec::with_capacity(n_rows);
+
+    for (time, _low, _high, _open, _close, _volume) in candles {
+        open_time.push(time * 1000);
+        close_time.push((time + granularity) * 1000 - 1);
+        open.push(_open as f32);
+        high.push(_high as f32);
+        low.push(_low as f32);
+        close.push(_close as f32);
+        volume.push(_volume as f32);
+    }
+
+    Ok(DataFrame::new(vec![
+        Series::new("open_time", open_time),
+        Series::new("close_time", close_time),
+        Series::new("open", open),
+        Series::new("high", high),
+        Series::new("low", low),
+        Series::new("close", close),
+        Series::new("volume", volume),
+    ])?)
+}
+
+impl MarketDataSource for CoinbaseClient {
+    type Error = CoinbaseError;
+
+    async fn symbols(&self) -> Result<Vec<String>, CoinbaseError> {
+        Ok(self
+            .products
+            .values()
+            .map(|product| canonical_symbol(&product.base_currency, &product.quote_currency))
+            .collect())
+    }
+
+    /// Pages through `GET /products/{id}/candles` in `MAX_CANDLES_PER_PAGE`
+    /// windows, since Coinbase caps a single response at 300 rows, and
+    /// concatenates the pages into one `DataFrame` via `polars` vstack.
+    async fn klines(
+        &self,
+        symbol: &str,
+        interval: Interval,
+        from: DateTime<Utc>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<DataFrame, CoinbaseError> {
+        let product_id = self.raw_symbol(symbol)?.to_owned();
+        let granularity = granularity_seconds(interval)?;
+        let to = to.unwrap_or_else(Utc::now);
+        let page_span = Duration::seconds(granularity * MAX_CANDLES_PER_PAGE);
+
+        let mut window_start = from;
+        let mut df: Option<DataFrame> = None;
+
+        while window_start < to {
+            let window_end = std::cmp::min(window_start + page_span, to);
+
+            let candles: Vec<Candle> = self
+                .client
+                .get(format!("https://api.exchange.coinbase.com/products/{product_id}/candles"))
+                .query(&[
+                    ("start", window_start.to_rfc3339()),
+                    ("end", window_end.to_rfc3339()),
+                    ("granularity", granularity.to_string()),
+                ])
+                .send()
+                .await?
+                .json()
+                .await?;
+
+            if !candles.is_empty() {
+                let page = candles_to_dataframe(candles, granularity)?;
+
+                df = Some(match df {
+                    Some(mut existing) => {
+                        existing.vstack_mut(&page)?;
+                        existing
+                    }
+                    None => page,
+                });
+            }
+
+            window_start = window_end;
+        }
+
+        Ok(df.unwrap_or_default())
+    }
+}
@@ -0,0 +1,6 @@
+//! Coinbase market data, implemented against the shared `MarketDataSource`
+//! trait so it's a drop-in alongside the `binance` client.
+
+mod client;
+
+pub(crate) use client::{CoinbaseClient, CoinbaseError};
@@ -0,0 +1,294 @@
+//! Compact fixed-width binary encoding for persisted klines and trades.
+//!
+//! JSON is fine for talking to an exchange API, but it's expensive to scan
+//! once a history spans months of 1m data. This packs each trade into a
+//! 32-byte record that can be memory-mapped and read sequentially without a
+//! parser, and does the same in bulk for klines alongside the existing
+//! seven-column `DataFrame` schema.
+
+use polars::prelude::*;
+
+use super::binance::BinanceError;
+
+const TRADE_RECORD_LEN: usize = 32;
+const KLINE_RECORD_LEN: usize = 36;
+
+/// Which side of the book a trade executed against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Side {
+    None = 0,
+    Bid = 1,
+    Ask = 2,
+}
+
+impl TryFrom<u8> for Side {
+    type Error = BinanceError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Side::None),
+            1 => Ok(Side::Bid),
+            2 => Ok(Side::Ask),
+            _ => Err(BinanceError::EncodingError(format!("invalid side byte: {value}"))),
+        }
+    }
+}
+
+/// A single trade, exchange- and asset-agnostic, ready to be packed into the
+/// fixed-width record below.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Trade {
+    pub exchange: u8,
+    pub base: u8,
+    pub quote: u8,
+    pub side: Side,
+    /// Exchange-reported server time, in nanoseconds; `None` when absent.
+    /// An absolute epoch value wouldn't fit the on-disk `u32` of
+    /// milliseconds (epoch ms already exceeds `u32::MAX`), so this is
+    /// stored as a millisecond *offset* from `time`, which only round-trips
+    /// when `server_time < time`, the offset is a whole number of
+    /// milliseconds, and it is under ~49 days. An offset under 1ms
+    /// (including `server_time == time`) is written as 0 and decodes as
+    /// `None`.
+    pub server_time: Option<u64>,
+    /// Event time, in nanoseconds.
+    pub time: u64,
+    pub price: f64,
+    pub amount: f64,
+}
+
+/// Packs a `Trade` into a 32-byte record:
+///
+/// | bytes  | field                                          |
+/// |--------|------------------------------------------------|
+/// | 0      | exchange code                                   |
+/// | 1      | base-currency code                              |
+/// | 2      | quote-currency code                             |
+/// | 3      | side (0=None, 1=Bid, 2=Ask)                      |
+/// | 4..8   | `time - server_time` offset, milliseconds, 0 = absent |
+/// | 8..16  | time, nanoseconds                                |
+/// | 16..24 | price                                            |
+/// | 24..32 | amount                                           |
+///
+/// Errors if `server_time` is after `time`, or the offset between them
+/// doesn't fit a `u32` of milliseconds.
+pub(crate) fn encode(trade: &Trade) -> Result<[u8; TRADE_RECORD_LEN], BinanceError> {
+    let mut buf = [0u8; TRADE_RECORD_LEN];
+
+    let offset_ms: u32 = match trade.server_time {
+        Some(server_time) => {
+            let offset_ns = trade.time.checked_sub(server_time).ok_or_else(|| {
+                BinanceError::EncodingError(format!(
+                    "server_time {server_time} is after event time {}",
+                    trade.time
+                ))
+            })?;
+
+            (offset_ns / 1_000_000).try_into().map_err(|_| {
+                BinanceError::EncodingError(format!(
+                    "server_time offset of {}ms does not fit a u32",
+                    offset_ns / 1_000_000
+                ))
+            })?
+        }
+        None => 0,
+    };
+
+    buf[0] = trade.exchange;
+    buf[1] = trade.base;
+    buf[2] = trade.quote;
+    buf[3] = trade.side as u8;
+    buf[4..8].copy_from_slice(&offset_ms.to_le_bytes());
+    buf[8..16].copy_from_slice(&trade.time.to_le_bytes());
+    buf[16..24].copy_from_slice(&trade.price.to_le_bytes());
+    buf[24..32].copy_from_slice(&trade.amount.to_le_bytes());
+
+    Ok(buf)
+}
+
+/// Unpacks a `Trade` from a 32-byte record, rejecting an out-of-range side
+/// byte or a record of the wrong length.
+pub(crate) fn decode(record: &[u8]) -> Result<Trade, BinanceError> {
+    if record.len() != TRADE_RECORD_LEN {
+        return Err(BinanceError::EncodingError(format!(
+            "expected a {TRADE_RECORD_LEN}-byte trade record, got {}",
+            record.len()
+        )));
+    }
+
+    let side = Side::try_from(record[3])?;
+
+    let offset_ms = u32::from_le_bytes(record[4..8].try_into().unwrap());
+    let time = u64::from_le_bytes(record[8..16].try_into().unwrap());
+    let server_time = (offset_ms != 0).then(|| time - offset_ms as u64 * 1_000_000);
+
+    let price = f64::from_le_bytes(record[16..24].try_into().unwrap());
+    let amount = f64::from_le_bytes(record[24..32].try_into().unwrap());
+
+    Ok(Trade {
+        exchange: record[0],
+        base: record[1],
+        quote: record[2],
+        side,
+        server_time,
+        time,
+        price,
+        amount,
+    })
+}
+
+/// Packs the seven kline columns into a flat buffer of fixed-width records,
+/// `i64` open/close times followed by `f32` OHLCV, for memory-mapped reads.
+pub(crate) fn encode_klines(df: &DataFrame) -> Result<Vec<u8>, BinanceError> {
+    let open_time = df.column("open_time")?.i64()?;
+    let close_time = df.column("close_time")?.i64()?;
+    let open = df.column("open")?.f32()?;
+    let high = df.column("high")?.f32()?;
+    let low = df.column("low")?.f32()?;
+    let close = df.column("close")?.f32()?;
+    let volume = df.column("volume")?.f32()?;
+
+    let mut buf = Vec::with_capacity(df.height() * KLINE_RECORD_LEN);
+
+    for i in 0..df.height() {
+        buf.extend_from_slice(&open_time.get(i).unwrap_or_default().to_le_bytes());
+        buf.extend_from_slice(&close_time.get(i).unwrap_or_default().to_le_bytes());
+        buf.extend_from_slice(&open.get(i).unwrap_or_default().to_le_bytes());
+        buf.extend_from_slice(&high.get(i).unwrap_or_default().to_le_bytes());
+        buf.extend_from_slice(&low.get(i).unwrap_or_default().to_le_bytes());
+        buf.extend_from_slice(&close.get(i).unwrap_or_default().to_le_bytes());
+        buf.extend_from_slice(&volume.get(i).unwrap_or_default().to_le_bytes());
+    }
+
+    Ok(buf)
+}
+
+/// Unpacks a buffer written by [`encode_klines`] back into a `DataFrame`.
+pub(crate) fn decode_klines(bytes: &[u8]) -> Result<DataFrame, BinanceError> {
+    if bytes.len() % KLINE_RECORD_LEN != 0 {
+        return Err(BinanceError::EncodingError(format!(
+            "kline buffer length {} is not a multiple of the {KLINE_RECORD_LEN}-byte record size",
+            bytes.len()
+        )));
+    }
+
+    let n_rows = bytes.len() / KLINE_RECORD_LEN;
+
+    let mut open_time: Vec<i64> = Vec::with_capacity(n_rows);
+    let mut close_time: Vec<i64> = Vec::with_capacity(n_rows);
+    let mut open: Vec<f32> = Vec::with_capacity(n_rows);
+    let mut high: Vec<f32> = Vec::with_capacity(n_rows);
+    let mut low: Vec<f32> = Vec::with_capacity(n_rows);
+    let mut close: Vec<f32> = Vec::with_capacity(n_rows);
+    let mut volume: Vec<f32> = Vec::with_capacity(n_rows);
+
+    for record in bytes.chunks_exact(KLINE_RECORD_LEN) {
+        open_time.push(i64::from_le_bytes(record[0..8].try_into().unwrap()));
+        close_time.push(i64::from_le_bytes(record[8..16].try_into().unwrap()));
+        open.push(f32::from_le_bytes(record[16..20].try_into().unwrap()));
+        high.push(f32::from_le_bytes(record[20..24].try_into().unwrap()));
+        low.push(f32::from_le_bytes(record[24..28].try_into().unwrap()));
+        close.push(f32::from_le_bytes(record[28..32].try_into().unwrap()));
+        volume.push(f32::from_le_bytes(record[32..36].try_into().unwrap()));
+    }
+
+    Ok(DataFrame::new(vec![
+        Series::new("open_time", open_time),
+        Series::new("close_time", close_time),
+        Series::new("open", open),
+        Series::new("high", high),
+        Series::new("low", low),
+        Series::new("close", close),
+        Series::new("volume", volume),
+    ])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trade_round_trips_through_encode_decode() {
+        let time = 1_700_000_000_123_456_789;
+
+        let trade = Trade {
+            exchange: 1,
+            base: 2,
+            quote: 3,
+            side: Side::Bid,
+            // An offset that's a whole number of milliseconds, since the
+            // on-disk offset only has millisecond resolution.
+            server_time: Some(time - 50_000_000),
+            time,
+            price: 42_123.45,
+            amount: 0.015,
+        };
+
+        let decoded = decode(&encode(&trade).unwrap()).unwrap();
+
+        assert_eq!(decoded, trade);
+    }
+
+    #[test]
+    fn absent_server_time_round_trips_to_none() {
+        let trade = Trade {
+            exchange: 0,
+            base: 0,
+            quote: 0,
+            side: Side::None,
+            server_time: None,
+            time: 0,
+            price: 0.0,
+            amount: 0.0,
+        };
+
+        let decoded = decode(&encode(&trade).unwrap()).unwrap();
+
+        assert_eq!(decoded.server_time, None);
+    }
+
+    #[test]
+    fn encode_rejects_server_time_after_event_time() {
+        let trade = Trade {
+            exchange: 0,
+            base: 0,
+            quote: 0,
+            side: Side::None,
+            server_time: Some(1_000_000_000),
+            time: 0,
+            price: 0.0,
+            amount: 0.0,
+        };
+
+        assert!(encode(&trade).is_err());
+    }
+
+    #[test]
+    fn encode_rejects_offset_too_large_for_u32_milliseconds() {
+        let trade = Trade {
+            exchange: 0,
+            base: 0,
+            quote: 0,
+            side: Side::None,
+            server_time: Some(0),
+            time: (u32::MAX as u64 + 1) * 1_000_000,
+            price: 0.0,
+            amount: 0.0,
+        };
+
+        assert!(encode(&trade).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_wrong_length_record() {
+        assert!(decode(&[0u8; 16]).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_invalid_side_byte() {
+        let mut record = [0u8; TRADE_RECORD_LEN];
+        record[3] = 9;
+
+        assert!(decode(&record).is_err());
+    }
+}
@@ -0,0 +1,41 @@
+//! A venue-agnostic view over market data, so downstream analysis code can
+//! depend on one trait instead of a concrete exchange client.
+
+use chrono::{DateTime, Utc};
+use polars::prelude::*;
+
+use super::binance::Interval;
+
+/// A source of market data for one exchange. `symbols()` and `klines()` both
+/// speak in canonical symbols (see [`canonical_symbol`]), so callers don't
+/// need to know that Binance spells a pair `BTCUSDT` while Coinbase spells
+/// the same pair `BTC-USDT`, just that both normalize to `BASE-QUOTE`.
+pub(crate) trait MarketDataSource {
+    type Error: std::error::Error;
+
+    /// The canonical symbols this exchange trades.
+    async fn symbols(&self) -> Result<Vec<String>, Self::Error>;
+
+    /// Klines for `symbol` between `from` and `to`, in the same seven-column
+    /// OHLCV schema regardless of exchange.
+    async fn klines(
+        &self,
+        symbol: &str,
+        interval: Interval,
+        from: DateTime<Utc>,
+        to: Option<DateTime<Utc>>,
+    ) -> Result<DataFrame, Self::Error>;
+}
+
+/// Builds the canonical `BASE-QUOTE` symbol for a `base`/`quote` pair.
+///
+/// This only normalizes the *separator* (Binance's concatenated `BTCUSDT`
+/// vs Coinbase's hyphenated `BTC-USD`), not the asset codes themselves:
+/// `USDT` and `USD` are different assets, and collapsing them would make
+/// `canonical_symbol` non-injective — two distinct real pairs (e.g.
+/// `BTCUSDT` and `BTCUSD`) would resolve to the same canonical string, and
+/// looking one back up would silently pick whichever the exchange's symbol
+/// map happened to iterate first.
+pub(crate) fn canonical_symbol(base: &str, quote: &str) -> String {
+    format!("{base}-{quote}")
+}
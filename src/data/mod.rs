@@ -0,0 +1,8 @@
+//! Market data: per-exchange clients, the shared `MarketDataSource` trait,
+//! and the on-disk encoding/storage that's exchange-agnostic.
+
+pub(crate) mod binance;
+pub(crate) mod coinbase;
+pub(crate) mod encoding;
+pub(crate) mod exchange;
+pub(crate) mod storage;
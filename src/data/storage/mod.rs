@@ -0,0 +1,34 @@
+//! Durable candle storage, so a backfill only has to fetch what's new.
+
+use std::ops::Range;
+
+use polars::prelude::*;
+
+use super::binance::{BinanceError, Interval};
+
+mod parquet;
+mod postgres;
+
+pub(crate) use parquet::ParquetStore;
+pub(crate) use postgres::PostgresStore;
+
+/// A place klines can be durably stored and incrementally extended, so
+/// `BinanceClient::backfill` only has to request candles newer than what's
+/// already saved here.
+pub(crate) trait CandleStore {
+    /// The most recent `open_time` already stored for `symbol`/`interval`,
+    /// or `None` if nothing has been stored yet.
+    async fn last_open_time(&self, symbol: &str, interval: Interval) -> Result<Option<i64>, BinanceError>;
+
+    /// Upserts `candles` for `symbol`/`interval`, keyed on `open_time`.
+    async fn insert(&self, symbol: &str, interval: Interval, candles: &DataFrame) -> Result<(), BinanceError>;
+
+    /// Loads stored candles for `symbol`/`interval` whose `open_time` falls
+    /// in `open_time_range`.
+    async fn load(
+        &self,
+        symbol: &str,
+        interval: Interval,
+        open_time_range: Range<i64>,
+    ) -> Result<DataFrame, BinanceError>;
+}
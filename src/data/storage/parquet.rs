@@ -0,0 +1,169 @@
+//! Parquet-on-disk `CandleStore`, one file per symbol/interval pair.
+
+use std::fs::File;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use polars::prelude::*;
+
+use super::super::binance::{BinanceError, Interval};
+use super::CandleStore;
+
+pub(crate) struct ParquetStore {
+    root: PathBuf,
+}
+
+impl ParquetStore {
+    pub(crate) fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn path_for(&self, symbol: &str, interval: Interval) -> PathBuf {
+        self.root.join(format!("{symbol}_{interval}.parquet"))
+    }
+
+    fn read(&self, symbol: &str, interval: Interval) -> Result<Option<DataFrame>, BinanceError> {
+        let path = self.path_for(symbol, interval);
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let file = File::open(path).map_err(|err| BinanceError::StorageError(err.to_string()))?;
+        let df = ParquetReader::new(file).finish()?;
+
+        Ok(Some(df))
+    }
+}
+
+impl CandleStore for ParquetStore {
+    async fn last_open_time(&self, symbol: &str, interval: Interval) -> Result<Option<i64>, BinanceError> {
+        let Some(df) = self.read(symbol, interval)? else {
+            return Ok(None);
+        };
+
+        Ok(df.column("open_time")?.i64()?.max())
+    }
+
+    async fn insert(&self, symbol: &str, interval: Interval, candles: &DataFrame) -> Result<(), BinanceError> {
+        let mut combined = match self.read(symbol, interval)? {
+            Some(mut existing) => {
+                existing.vstack_mut(candles)?;
+                existing
+            }
+            None => candles.clone(),
+        };
+
+        // `candles` is appended after any existing rows, so keeping the
+        // last occurrence of each `open_time` prefers the new data over
+        // what was already stored -- an upsert, not a plain append.
+        combined = combined.unique_stable(Some(&["open_time".to_owned()]), UniqueKeepStrategy::Last, None)?;
+        combined = combined.sort(["open_time"], false, false)?;
+
+        std::fs::create_dir_all(&self.root).map_err(|err| BinanceError::StorageError(err.to_string()))?;
+
+        let file = File::create(self.path_for(symbol, interval))
+            .map_err(|err| BinanceError::StorageError(err.to_string()))?;
+
+        ParquetWriter::new(file).finish(&mut combined)?;
+
+        Ok(())
+    }
+
+    async fn load(
+        &self,
+        symbol: &str,
+        interval: Interval,
+        open_time_range: Range<i64>,
+    ) -> Result<DataFrame, BinanceError> {
+        let Some(df) = self.read(symbol, interval)? else {
+            return Ok(DataFrame::default());
+        };
+
+        let open_time = df.column("open_time")?.i64()?;
+        let mask = open_time.gt_eq(open_time_range.start) & open_time.lt(open_time_range.end);
+
+        Ok(df.filter(&mask)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    /// A throwaway `ParquetStore` under the system temp dir, unique per
+    /// test run so parallel test runs don't collide.
+    fn temp_store() -> ParquetStore {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+
+        ParquetStore::new(std::env::temp_dir().join(format!("mono-trading-parquet-store-test-{nanos}")))
+    }
+
+    fn candle_frame(rows: &[(i64, i64, f32, f32, f32, f32, f32)]) -> DataFrame {
+        let mut open_time = Vec::with_capacity(rows.len());
+        let mut close_time = Vec::with_capacity(rows.len());
+        let mut open = Vec::with_capacity(rows.len());
+        let mut high = Vec::with_capacity(rows.len());
+        let mut low = Vec::with_capacity(rows.len());
+        let mut close = Vec::with_capacity(rows.len());
+        let mut volume = Vec::with_capacity(rows.len());
+
+        for &(_open_time, _close_time, _open, _high, _low, _close, _volume) in rows {
+            open_time.push(_open_time);
+            close_time.push(_close_time);
+            open.push(_open);
+            high.push(_high);
+            low.push(_low);
+            close.push(_close);
+            volume.push(_volume);
+        }
+
+        DataFrame::new(vec![
+            Series::new("open_time", open_time),
+            Series::new("close_time", close_time),
+            Series::new("open", open),
+            Series::new("high", high),
+            Series::new("low", low),
+            Series::new("close", close),
+            Series::new("volume", volume),
+        ])
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn insert_upserts_overlapping_batches_instead_of_duplicating() {
+        let store = temp_store();
+        let symbol = "BTC-USD";
+
+        let first = candle_frame(&[
+            (0, 59_999, 1.0, 1.0, 1.0, 1.0, 1.0),
+            (60_000, 119_999, 2.0, 2.0, 2.0, 2.0, 2.0),
+        ]);
+        store.insert(symbol, Interval::OneMinute, &first).await.unwrap();
+
+        // Overlaps the open_time=60_000 row with an updated close, and adds
+        // one genuinely new row.
+        let second = candle_frame(&[
+            (60_000, 119_999, 2.5, 2.5, 2.5, 2.5, 2.5),
+            (120_000, 179_999, 3.0, 3.0, 3.0, 3.0, 3.0),
+        ]);
+        store.insert(symbol, Interval::OneMinute, &second).await.unwrap();
+
+        let last_open_time = store.last_open_time(symbol, Interval::OneMinute).await.unwrap();
+        assert_eq!(last_open_time, Some(120_000));
+
+        let loaded = store.load(symbol, Interval::OneMinute, 0..1_000_000).await.unwrap();
+        assert_eq!(loaded.height(), 3, "overlapping open_time should upsert, not duplicate");
+
+        let open_time = loaded.column("open_time").unwrap().i64().unwrap();
+        let close = loaded.column("close").unwrap().f32().unwrap();
+
+        let updated_row = (0..loaded.height())
+            .find(|&i| open_time.get(i) == Some(60_000))
+            .expect("open_time=60_000 row should still be present");
+
+        assert_eq!(close.get(updated_row), Some(2.5), "duplicate open_time should keep the newer value");
+    }
+}
@@ -0,0 +1,193 @@
+//! Postgres-backed `CandleStore`, via `tokio-postgres`.
+
+use std::ops::Range;
+
+use polars::prelude::*;
+use tokio_postgres::{Config, NoTls};
+
+use super::super::binance::{BinanceError, Interval};
+use super::CandleStore;
+
+const CREATE_TABLE: &str = "
+    CREATE TABLE IF NOT EXISTS candles (
+        symbol TEXT NOT NULL,
+        interval TEXT NOT NULL,
+        open_time BIGINT NOT NULL,
+        close_time BIGINT NOT NULL,
+        open REAL NOT NULL,
+        high REAL NOT NULL,
+        low REAL NOT NULL,
+        close REAL NOT NULL,
+        volume REAL NOT NULL,
+        PRIMARY KEY (symbol, interval, open_time)
+    )
+";
+
+const UPSERT: &str = "
+    INSERT INTO candles (symbol, interval, open_time, close_time, open, high, low, close, volume)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+    ON CONFLICT (symbol, interval, open_time) DO UPDATE SET
+        close_time = EXCLUDED.close_time,
+        open = EXCLUDED.open,
+        high = EXCLUDED.high,
+        low = EXCLUDED.low,
+        close = EXCLUDED.close,
+        volume = EXCLUDED.volume
+";
+
+pub(crate) struct PostgresStore {
+    client: tokio_postgres::Client,
+}
+
+impl PostgresStore {
+    /// Connects using the standard `PG*` environment variables (`PGHOST`,
+    /// `PGPORT`, `PGUSER`, `PGPASSWORD`, `PGDATABASE`) and ensures the
+    /// `candles` table exists. The connection is always made without TLS
+    /// (`NoTls`); SSL connections are not supported.
+    pub(crate) async fn connect() -> Result<Self, BinanceError> {
+        let mut config = Config::new();
+
+        if let Ok(host) = std::env::var("PGHOST") {
+            config.host(&host);
+        }
+
+        if let Ok(port) = std::env::var("PGPORT") {
+            let port: u16 = port
+                .parse()
+                .map_err(|_| BinanceError::StorageError(format!("invalid PGPORT: {port}")))?;
+            config.port(port);
+        }
+
+        if let Ok(user) = std::env::var("PGUSER") {
+            config.user(&user);
+        }
+
+        if let Ok(password) = std::env::var("PGPASSWORD") {
+            config.password(&password);
+        }
+
+        if let Ok(dbname) = std::env::var("PGDATABASE") {
+            config.dbname(&dbname);
+        }
+
+        let (client, connection) = config
+            .connect(NoTls)
+            .await
+            .map_err(|err| BinanceError::StorageError(err.to_string()))?;
+
+        tokio::spawn(async move {
+            if let Err(err) = connection.await {
+                eprintln!("postgres connection error: {err}");
+            }
+        });
+
+        client
+            .execute(CREATE_TABLE, &[])
+            .await
+            .map_err(|err| BinanceError::StorageError(err.to_string()))?;
+
+        Ok(Self { client })
+    }
+}
+
+impl CandleStore for PostgresStore {
+    async fn last_open_time(&self, symbol: &str, interval: Interval) -> Result<Option<i64>, BinanceError> {
+        let interval = interval.to_string();
+
+        let row = self
+            .client
+            .query_opt(
+                "SELECT max(open_time) FROM candles WHERE symbol = $1 AND interval = $2",
+                &[&symbol, &interval],
+            )
+            .await
+            .map_err(|err| BinanceError::StorageError(err.to_string()))?;
+
+        Ok(row.and_then(|row| row.get::<_, Option<i64>>(0)))
+    }
+
+    async fn insert(&self, symbol: &str, interval: Interval, candles: &DataFrame) -> Result<(), BinanceError> {
+        let interval = interval.to_string();
+
+        let open_time = candles.column("open_time")?.i64()?;
+        let close_time = candles.column("close_time")?.i64()?;
+        let open = candles.column("open")?.f32()?;
+        let high = candles.column("high")?.f32()?;
+        let low = candles.column("low")?.f32()?;
+        let close = candles.column("close")?.f32()?;
+        let volume = candles.column("volume")?.f32()?;
+
+        for i in 0..candles.height() {
+            self.client
+                .execute(
+                    UPSERT,
+                    &[
+                        &symbol,
+                        &interval,
+                        &open_time.get(i),
+                        &close_time.get(i),
+                        &open.get(i),
+                        &high.get(i),
+                        &low.get(i),
+                        &close.get(i),
+                        &volume.get(i),
+                    ],
+                )
+                .await
+                .map_err(|err| BinanceError::StorageError(err.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    async fn load(
+        &self,
+        symbol: &str,
+        interval: Interval,
+        open_time_range: Range<i64>,
+    ) -> Result<DataFrame, BinanceError> {
+        let interval = interval.to_string();
+
+        let rows = self
+            .client
+            .query(
+                "SELECT open_time, close_time, open, high, low, close, volume
+                 FROM candles
+                 WHERE symbol = $1 AND interval = $2 AND open_time >= $3 AND open_time < $4
+                 ORDER BY open_time",
+                &[&symbol, &interval, &open_time_range.start, &open_time_range.end],
+            )
+            .await
+            .map_err(|err| BinanceError::StorageError(err.to_string()))?;
+
+        let n_rows = rows.len();
+
+        let mut open_time: Vec<i64> = Vec::with_capacity(n_rows);
+        let mut close_time: Vec<i64> = Vec::with_capacity(n_rows);
+        let mut open: Vec<f32> = Vec::with_capacity(n_rows);
+        let mut high: Vec<f32> = Vec::with_capacity(n_rows);
+        let mut low: Vec<f32> = Vec::with_capacity(n_rows);
+        let mut close: Vec<f32> = Vec::with_capacity(n_rows);
+        let mut volume: Vec<f32> = Vec::with_capacity(n_rows);
+
+        for row in rows {
+            open_time.push(row.get(0));
+            close_time.push(row.get(1));
+            open.push(row.get(2));
+            high.push(row.get(3));
+            low.push(row.get(4));
+            close.push(row.get(5));
+            volume.push(row.get(6));
+        }
+
+        Ok(DataFrame::new(vec![
+            Series::new("open_time", open_time),
+            Series::new("close_time", close_time),
+            Series::new("open", open),
+            Series::new("high", high),
+            Series::new("low", low),
+            Series::new("close", close),
+            Series::new("volume", volume),
+        ])?)
+    }
+}